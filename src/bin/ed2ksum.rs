@@ -0,0 +1,50 @@
+//! A small CLI tool that prints ED2K hashes for files, in the same
+//! lowercase hex format as `{:x}` on the hash types.
+
+use std::env;
+use std::process::ExitCode;
+
+use ed2k::{Ed2kBlue, Ed2kRed, Ed2kRedBlue};
+
+#[derive(Clone, Copy)]
+enum Flavor {
+    Red,
+    Blue,
+    RedBlue,
+}
+
+fn main() -> ExitCode {
+    let mut flavor = Flavor::Blue;
+    let mut paths = Vec::new();
+
+    for arg in env::args().skip(1) {
+        match arg.as_str() {
+            "--red" => flavor = Flavor::Red,
+            "--blue" => flavor = Flavor::Blue,
+            "--red-blue" => flavor = Flavor::RedBlue,
+            _ => paths.push(arg),
+        }
+    }
+
+    if paths.is_empty() {
+        eprintln!("usage: ed2ksum [--red|--blue|--red-blue] <file>...");
+        return ExitCode::FAILURE;
+    }
+
+    let mut status = ExitCode::SUCCESS;
+    for path in paths {
+        let hash = match flavor {
+            Flavor::Red => Ed2kRed::digest_file(&path).map(|h| format!("{h:x}")),
+            Flavor::Blue => Ed2kBlue::digest_file(&path).map(|h| format!("{h:x}")),
+            Flavor::RedBlue => Ed2kRedBlue::digest_file(&path).map(|h| format!("{h:x}")),
+        };
+        match hash {
+            Ok(hash) => println!("{hash}  {path}"),
+            Err(err) => {
+                eprintln!("ed2ksum: {path}: {err}");
+                status = ExitCode::FAILURE;
+            }
+        }
+    }
+    status
+}
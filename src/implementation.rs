@@ -6,15 +6,23 @@ use digest::{
     FixedOutput, FixedOutputReset, HashMarker, OutputSizeUser, Reset, Update,
 };
 use md4::Md4;
+#[cfg(feature = "zeroize")]
+use zeroize::Zeroize;
 
 const CHUNK_SIZE: usize = 9728000;
 type Array<T> = GenericArray<u8, T>;
 
+/// A single chunk's MD4 hash, as used in the ED2K "hash set".
+pub type ChunkHash = Array<U16>;
+
 #[derive(Default, Debug, Clone)]
 struct ChunkList {
     hasher: Md4,
     first_chunk: Array<U16>,
     chunk_counter: u64,
+    /// Ordered per-chunk hashes, kept only when hash-set collection is
+    /// enabled. `None` keeps the default hasher's O(1) memory footprint.
+    collected: Option<Vec<ChunkHash>>,
 }
 impl ChunkList {
     fn add_chunk(&mut self, hash: &Array<U16>) {
@@ -23,11 +31,24 @@ impl ChunkList {
         }
         self.chunk_counter += 1;
         self.hasher.update(hash);
+        if let Some(collected) = &mut self.collected {
+            collected.push(*hash);
+        }
     }
     fn reset(&mut self) {
-        self.hasher.reset();
-        self.chunk_counter = 0;
-        self.first_chunk.fill(0);
+        #[cfg(feature = "zeroize")]
+        {
+            self.zeroize();
+        }
+        #[cfg(not(feature = "zeroize"))]
+        {
+            self.hasher.reset();
+            self.chunk_counter = 0;
+            self.first_chunk.fill(0);
+            if let Some(collected) = &mut self.collected {
+                collected.clear();
+            }
+        }
     }
     fn chunk_counter(&self) -> u64 {
         self.chunk_counter
@@ -39,6 +60,12 @@ impl ChunkList {
     fn copy_list_hash_reset(&mut self, out: &mut Array<U16>) {
         self.hasher.finalize_into_reset(out);
     }
+    fn enable_collecting(&mut self) {
+        self.collected = Some(Vec::new());
+    }
+    fn take_collected(&mut self) -> Option<Vec<ChunkHash>> {
+        self.collected.take()
+    }
 }
 
 /// Abstraction over the ED2K hash flavor
@@ -124,6 +151,44 @@ impl Ed2kState {
         self.chunk_len = 0;
         self.chunk_list.add_chunk(&hash);
     }
+
+    /// Captures a checkpoint of this state, for later use with
+    /// [`Ed2kImpl::restore`].
+    ///
+    /// Returns `None` unless the state currently sits on a chunk boundary
+    /// (no partial chunk data buffered), since the in-progress MD4 chunk
+    /// hasher has no exposed intermediate state to serialize. Once past the
+    /// first chunk, it also returns `None` unless the hasher was created
+    /// with [`Ed2kImpl::new_collecting_hashset`], because the ordered chunk
+    /// hashes are the only way to rebuild the running MD4 state over
+    /// completed chunks on restore.
+    pub fn checkpoint(&self) -> Option<Checkpoint> {
+        if self.chunk_len != 0 {
+            return None;
+        }
+        let chunk_hashes = if self.chunk_list.chunk_counter == 0 {
+            Vec::new()
+        } else {
+            self.chunk_list.collected.clone()?
+        };
+        Some(Checkpoint {
+            first_chunk: self.chunk_list.first_chunk,
+            chunk_counter: self.chunk_list.chunk_counter,
+            chunk_hashes,
+        })
+    }
+}
+
+/// A serializable snapshot of [`Ed2kState`] at a chunk boundary, for
+/// resuming hashing of a large file across process restarts or for
+/// distributing the work. See [`Ed2kState::checkpoint`] and
+/// [`Ed2kImpl::restore`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Checkpoint {
+    first_chunk: ChunkHash,
+    chunk_counter: u64,
+    chunk_hashes: Vec<ChunkHash>,
 }
 
 impl<C> FixedOutput for Ed2kImpl<C>
@@ -165,6 +230,136 @@ where
     }
 }
 
+impl<C> Ed2kImpl<C>
+where
+    C: Ed2kColor,
+{
+    /// Creates a hasher that, in addition to the root hash, retains every
+    /// per-chunk MD4 hash (the ED2K "hash set"), so it can be retrieved
+    /// later via [`Ed2kImpl::finalize_with_hashset`]. The default hasher
+    /// does not do this, keeping its O(1) memory footprint.
+    pub fn new_collecting_hashset() -> Self {
+        let mut this = Self::default();
+        this.state.chunk_list.enable_collecting();
+        this
+    }
+
+    /// Finalizes the hash, returning the root hash together with the
+    /// ordered per-chunk MD4 hashes ("hash set"), if collection was enabled
+    /// via [`Ed2kImpl::new_collecting_hashset`]. The hash set is what real
+    /// ED2K clients use to build `ed2k://` links and to verify individual
+    /// parts of a partially downloaded file.
+    pub fn finalize_with_hashset(mut self) -> (Array<C::OutputSize>, Option<Vec<ChunkHash>>) {
+        let mut out = Array::<C::OutputSize>::default();
+        C::finalize_ref(&mut self.state, &mut out);
+        (out, self.state.chunk_list.take_collected())
+    }
+
+    /// Captures a checkpoint of this hasher, for later use with
+    /// [`Ed2kImpl::restore`]. See [`Ed2kState::checkpoint`] for when this
+    /// returns `None`.
+    pub fn checkpoint(&self) -> Option<Checkpoint> {
+        self.state.checkpoint()
+    }
+
+    /// Resumes hashing from a [`Checkpoint`] produced by
+    /// [`Ed2kImpl::checkpoint`], returning a hasher primed to the same
+    /// chunk boundary. Hash-set collection is re-enabled on the result,
+    /// since it is what made the checkpoint possible in the first place.
+    pub fn restore(cp: &Checkpoint) -> Self {
+        let mut chunk_list = ChunkList {
+            first_chunk: cp.first_chunk,
+            collected: Some(Vec::new()),
+            ..ChunkList::default()
+        };
+        for hash in &cp.chunk_hashes {
+            chunk_list.add_chunk(hash);
+        }
+        debug_assert_eq!(chunk_list.chunk_counter, cp.chunk_counter);
+
+        Self {
+            state: Ed2kState {
+                chunk_hasher: Md4::default(),
+                chunk_len: 0,
+                chunk_list,
+            },
+            _color: Default::default(),
+        }
+    }
+}
+
+#[cfg(feature = "mmap")]
+impl<C> Ed2kImpl<C>
+where
+    C: Ed2kColor,
+{
+    /// Hashes the file at `path`. Every non-empty file is memory-mapped;
+    /// empty files, which cannot be mapped, and files whose mapping fails
+    /// are read with buffered reads instead. There is no size threshold:
+    /// small non-empty files are memory-mapped as well.
+    pub fn digest_file(path: impl AsRef<std::path::Path>) -> std::io::Result<Array<C::OutputSize>> {
+        use digest::Digest;
+        use std::io::Read;
+
+        let file = std::fs::File::open(path)?;
+        let len = file.metadata()?.len();
+
+        if len > 0 {
+            if let Ok(mmap) = unsafe { memmap2::Mmap::map(&file) } {
+                return Ok(Self::digest(&mmap[..]));
+            }
+        }
+
+        let mut buf = Vec::new();
+        std::io::BufReader::new(file).read_to_end(&mut buf)?;
+        Ok(Self::digest(&buf))
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<C> Ed2kImpl<C>
+where
+    C: Ed2kColor,
+{
+    /// Hashes `data` using multiple threads via `rayon`.
+    ///
+    /// The input is split into `CHUNK_SIZE` slices, each of which is
+    /// MD4-hashed independently with `par_iter`. The resulting chunk hashes
+    /// are then fed into a [`ChunkList`] in order, and any trailing partial
+    /// chunk is hashed into the running chunk hasher, before the
+    /// flavor-specific [`Ed2kColor::finalize_ref`] is run. This produces
+    /// bit-identical output to the sequential `Update`/`FixedOutput` path for
+    /// every input, including inputs that are an exact multiple of the
+    /// chunk size.
+    pub fn digest_parallel(data: &[u8]) -> Array<C::OutputSize> {
+        use digest::Digest;
+        use rayon::prelude::*;
+
+        let full_chunks: Vec<&[u8]> = data.chunks_exact(CHUNK_SIZE).collect();
+        let remainder = &data[full_chunks.len() * CHUNK_SIZE..];
+
+        let hashes: Vec<Array<U16>> = full_chunks.into_par_iter().map(Md4::digest).collect();
+
+        let mut chunk_list = ChunkList::default();
+        for hash in &hashes {
+            chunk_list.add_chunk(hash);
+        }
+
+        let mut chunk_hasher = Md4::default();
+        Update::update(&mut chunk_hasher, remainder);
+
+        let mut state = Ed2kState {
+            chunk_hasher,
+            chunk_len: remainder.len(),
+            chunk_list,
+        };
+
+        let mut out = Array::<C::OutputSize>::default();
+        C::finalize_ref(&mut state, &mut out);
+        out
+    }
+}
+
 impl Ed2kColor for Red {
     type OutputSize = U16;
     fn finalize_ref(state: &mut Ed2kState, out: &mut Array<U16>) {
@@ -267,3 +462,214 @@ impl Ed2kColor for RedBlue {
         state.chunk_list.copy_list_hash_reset(red_out);
     }
 }
+
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for ChunkList {
+    fn zeroize(&mut self) {
+        self.first_chunk.zeroize();
+        self.chunk_counter.zeroize();
+        if let Some(collected) = &mut self.collected {
+            collected.zeroize();
+        }
+        // `Md4` exposes no internal buffer to zeroize directly, so the best
+        // we can do is discard its working state back to the initial digest.
+        self.hasher.reset();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for Ed2kState {
+    fn zeroize(&mut self) {
+        self.chunk_hasher.reset();
+        self.chunk_len.zeroize();
+        self.chunk_list.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for Ed2kState {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for Ed2kState {}
+
+#[cfg(test)]
+mod checkpoint_tests {
+    use super::*;
+
+    #[test]
+    fn restore_matches_continuous_hashing() {
+        let first = vec![0x42; CHUNK_SIZE];
+        let second = vec![0x24; CHUNK_SIZE / 2];
+
+        let mut continuous = Ed2kImpl::<Blue>::default();
+        Update::update(&mut continuous, &first);
+        Update::update(&mut continuous, &second);
+        let expected = continuous.finalize_fixed();
+
+        let mut hasher = Ed2kImpl::<Blue>::new_collecting_hashset();
+        Update::update(&mut hasher, &first);
+        let cp = hasher.checkpoint().expect("state is at a chunk boundary");
+
+        let mut resumed = Ed2kImpl::<Blue>::restore(&cp);
+        Update::update(&mut resumed, &second);
+        let actual = resumed.finalize_fixed();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn checkpoint_serde_round_trip() {
+        let first = vec![0x42; CHUNK_SIZE];
+        let second = vec![0x24; CHUNK_SIZE / 2];
+
+        let mut hasher = Ed2kImpl::<Blue>::new_collecting_hashset();
+        Update::update(&mut hasher, &first);
+        let cp = hasher.checkpoint().expect("state is at a chunk boundary");
+
+        let json = serde_json::to_string(&cp).unwrap();
+        let decoded: Checkpoint = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, cp);
+
+        let mut continuous = Ed2kImpl::<Blue>::default();
+        Update::update(&mut continuous, &first);
+        Update::update(&mut continuous, &second);
+        let expected = continuous.finalize_fixed();
+
+        let mut resumed = Ed2kImpl::<Blue>::restore(&decoded);
+        Update::update(&mut resumed, &second);
+        let actual = resumed.finalize_fixed();
+
+        assert_eq!(actual, expected);
+    }
+}
+
+#[cfg(all(test, feature = "rayon"))]
+mod parallel_tests {
+    use super::*;
+    use digest::Digest;
+
+    fn check<C: Ed2kColor>(data: &[u8]) {
+        let sequential = Ed2kImpl::<C>::digest(data);
+        let parallel = Ed2kImpl::<C>::digest_parallel(data);
+        assert_eq!(sequential, parallel);
+    }
+
+    #[test]
+    fn matches_sequential_hashing() {
+        for len in [
+            0,
+            412,
+            CHUNK_SIZE,
+            CHUNK_SIZE + 412,
+            CHUNK_SIZE * 2,
+            CHUNK_SIZE * 2 + 412,
+        ] {
+            let data = vec![0x55; len];
+            check::<Red>(&data);
+            check::<Blue>(&data);
+            check::<RedBlue>(&data);
+        }
+    }
+}
+
+#[cfg(all(test, feature = "mmap"))]
+mod mmap_tests {
+    use super::*;
+    use digest::Digest;
+    use std::io::Write;
+
+    fn write_temp(name: &str, contents: &[u8]) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("ed2k_digest_file_test_{name}"));
+        let mut file = std::fs::File::create(&path).unwrap();
+        file.write_all(contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn digest_file_matches_digest_for_mapped_file() {
+        let data = vec![0x55; CHUNK_SIZE + 412];
+        let path = write_temp("mapped", &data);
+
+        let expected = Ed2kImpl::<Blue>::digest(&data);
+        let actual = Ed2kImpl::<Blue>::digest_file(&path).unwrap();
+
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn digest_file_matches_digest_for_empty_file() {
+        let path = write_temp("empty", &[]);
+
+        let expected = Ed2kImpl::<Blue>::digest([]);
+        let actual = Ed2kImpl::<Blue>::digest_file(&path).unwrap();
+
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(actual, expected);
+    }
+}
+
+#[cfg(all(test, feature = "zeroize"))]
+mod zeroize_tests {
+    use super::*;
+    use digest::Digest;
+
+    #[test]
+    fn chunk_list_zeroize_clears_sensitive_fields() {
+        let mut list = ChunkList::default();
+        list.enable_collecting();
+        let hash = Md4::digest(b"some chunk data");
+        list.add_chunk(&hash);
+
+        assert_ne!(list.first_chunk, Array::<U16>::default());
+        assert_eq!(list.chunk_counter, 1);
+        assert_eq!(list.collected.as_deref(), Some(&[hash][..]));
+
+        list.zeroize();
+
+        assert_eq!(list.first_chunk, Array::<U16>::default());
+        assert_eq!(list.chunk_counter, 0);
+        assert_eq!(list.collected.as_deref(), Some(&[][..]));
+    }
+
+    #[test]
+    fn ed2k_state_zeroize_clears_buffered_chunk_and_list() {
+        let mut state = Ed2kState {
+            chunk_hasher: Md4::default(),
+            chunk_len: 0,
+            chunk_list: ChunkList::default(),
+        };
+        state.chunk_list.enable_collecting();
+        Update::update(&mut state.chunk_hasher, b"partial chunk bytes");
+        state.chunk_len = b"partial chunk bytes".len();
+        state.chunk_list.add_chunk(&Md4::digest(b"a prior chunk"));
+
+        state.zeroize();
+
+        assert_eq!(state.chunk_len, 0);
+        assert_eq!(state.chunk_list.chunk_counter, 0);
+        assert_eq!(state.chunk_list.first_chunk, Array::<U16>::default());
+        assert_eq!(state.chunk_list.collected.as_deref(), Some(&[][..]));
+        // the in-progress chunk hasher was discarded back to its initial state
+        assert_eq!(state.chunk_hasher.clone().finalize_fixed(), Md4::digest([]));
+    }
+
+    #[test]
+    fn ed2k_impl_reset_zeroizes_state() {
+        let mut hasher = Ed2kImpl::<Blue>::default();
+        hasher.state.chunk_list.enable_collecting();
+        Update::update(&mut hasher, b"less than a full chunk");
+
+        Reset::reset(&mut hasher);
+
+        assert_eq!(hasher.state.chunk_len, 0);
+        assert_eq!(hasher.state.chunk_list.chunk_counter, 0);
+        assert_eq!(hasher.state.chunk_list.first_chunk, Array::<U16>::default());
+        assert_eq!(hasher.state.chunk_list.collected.as_deref(), Some(&[][..]));
+    }
+}
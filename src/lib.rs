@@ -32,7 +32,7 @@ This crate implements both forms, as well as an efficient way to compute both at
   efficentily computing both ED2K hash flavors at once.
 */
 
-use crate::implementation::{Blue, Ed2kImpl, Red, RedBlue};
+use crate::implementation::{Blue, ChunkHash, Ed2kImpl, Red, RedBlue};
 
 /// The "official" ED2K hashing algorithm. Identical to Ed2kBlue.
 pub type Ed2k = Ed2kBlue;
@@ -48,6 +48,24 @@ pub use digest;
 
 pub mod implementation;
 
+/// Formats an `ed2k://` link from a file name, its byte length, its root
+/// hash, and its ordered per-chunk hash set.
+///
+/// The root hash and hash set are obtained from
+/// [`Ed2kImpl::finalize_with_hashset`](implementation::Ed2kImpl::finalize_with_hashset).
+/// The resulting link has the form
+/// `ed2k://|file|<name>|<length>|<root hash>|h=<chunk hashes>|/`, which real
+/// ED2K clients use to reference a file and verify individually downloaded
+/// chunks against its hash set.
+pub fn format_ed2k_link(name: &str, length: u64, root_hash: &ChunkHash, hash_set: &[ChunkHash]) -> String {
+    let hash_set = hash_set
+        .iter()
+        .map(|hash| format!("{hash:x}"))
+        .collect::<Vec<_>>()
+        .join(",");
+    format!("ed2k://|file|{name}|{length}|{root_hash:x}|h={hash_set}|/")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -183,3 +201,55 @@ mod tests {
         }
     }
 }
+
+#[cfg(test)]
+mod hashset_tests {
+    use super::*;
+    use digest::Digest;
+    use md4::Md4;
+
+    const CHUNK_SIZE: usize = 9728000;
+
+    #[test]
+    fn finalize_with_hashset_matches_digest_and_chunks() {
+        let mut data = vec![0x55; CHUNK_SIZE + 412];
+        data.extend(vec![0xaa; CHUNK_SIZE]);
+
+        let expected_root = Ed2kBlue::digest(&data);
+        let expected_chunks: Vec<_> = data.chunks(CHUNK_SIZE).map(Md4::digest).collect();
+
+        let mut hasher = Ed2kBlue::new_collecting_hashset();
+        hasher.update(&data);
+        let (root, chunk_hashes) = hasher.finalize_with_hashset();
+
+        assert_eq!(root, expected_root);
+        assert_eq!(chunk_hashes, Some(expected_chunks));
+    }
+
+    #[test]
+    fn format_ed2k_link_matches_expected_format() {
+        let data = vec![0x55; CHUNK_SIZE + 412];
+
+        let mut hasher = Ed2kBlue::new_collecting_hashset();
+        hasher.update(&data);
+        let (root, chunk_hashes) = hasher.finalize_with_hashset();
+        let chunk_hashes = chunk_hashes.unwrap();
+
+        let link = format_ed2k_link("foo.bin", data.len() as u64, &root, &chunk_hashes);
+
+        let expected_hashes = chunk_hashes
+            .iter()
+            .map(|hash| format!("{hash:x}"))
+            .collect::<Vec<_>>()
+            .join(",");
+        assert_eq!(
+            link,
+            format!(
+                "ed2k://|file|foo.bin|{}|{:x}|h={}|/",
+                data.len(),
+                root,
+                expected_hashes
+            )
+        );
+    }
+}